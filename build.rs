@@ -3,6 +3,11 @@ use std::io::{self, Write};
 use std::path::Path;
 use std::env;
 
+#[path = "src/codec.rs"]
+mod codec;
+
+use codec::{BitPackedWriter, FieldTag};
+
 // Define constants for the directory and input file name
 const OUTPUT_DIR: &str = "build/";
 const FILE_NAME: &str = "input.bin";
@@ -10,54 +15,59 @@ const FILE_NAME: &str = "input.bin";
 fn main() -> io::Result<()> {
     println!("🔧 Flappy Bird ZisK Input Generator");
     println!("=====================================");
-    
+
     // Get command line arguments for score data
     let args: Vec<String> = env::args().collect();
-    
+
     if args.len() != 4 {
         println!("❌ Usage: cargo run --bin build <player_id> <score> <difficulty>");
         println!("   Example: cargo run --bin build player_123 15 3");
         return Ok(());
     }
-    
+
     let player_id = &args[1];
     let score: u32 = args[2].parse().unwrap_or(0);
     let difficulty: u8 = args[3].parse().unwrap_or(1);
-    
+
     println!("📊 Generating input for:");
     println!("   Player ID: {}", player_id);
     println!("   Score: {}", score);
     println!("   Difficulty: {}", difficulty);
-    
+
     // Ensure the output directory exists
     let output_dir = Path::new(OUTPUT_DIR);
     if !output_dir.exists() {
         println!("📁 Creating output directory: {}", OUTPUT_DIR);
-        fs::create_dir_all(output_dir)?; 
+        fs::create_dir_all(output_dir)?;
     }
 
-    // Create the file and write the score data
+    // Encode the payload with the shared bit-packed codec instead of
+    // hand-placing byte offsets: a version header, then tag-prefixed fields.
+    let mut writer = BitPackedWriter::new();
+    writer.write_version();
+
+    writer.write_tag(FieldTag::PlayerId);
+    writer.write_uvarint(player_id.len() as u64);
+    writer.byte_align();
+    writer.write_aligned_bytes(player_id.as_bytes());
+
+    writer.write_tag(FieldTag::Score);
+    writer.write_bits(score as u64, 32);
+
+    writer.write_tag(FieldTag::Difficulty);
+    writer.write_bits(difficulty as u64, 8);
+
+    let payload = writer.into_bytes();
+
+    // Create the file and write the encoded payload
     let file_path = output_dir.join(FILE_NAME);
     let mut file = File::create(&file_path)?;
-    
-    // Write player_id_length (1 byte)
-    let player_id_length = player_id.len() as u8;
-    file.write_all(&[player_id_length])?;
-    
-    // Write player_id bytes (variable length)
-    file.write_all(player_id.as_bytes())?;
-    
-    // Write score (4 bytes, little-endian)
-    file.write_all(&score.to_le_bytes())?;
-    
-    // Write difficulty (1 byte)
-    file.write_all(&[difficulty])?;
-    
+    file.write_all(&payload)?;
+
     println!("💾 Input file generated: {}", file_path.display());
     println!("📏 File size: {} bytes", file_path.metadata()?.len());
-    println!("📊 Input format: [length: {}][player_id: {}][score: {}][difficulty: {}]", 
-             player_id_length, player_id, score, difficulty);
+    println!("📊 Input format: [version: 1][player_id tag+len+bytes][score tag+u32][difficulty tag+u8]");
     println!("✅ Input generation complete!");
-    
+
     Ok(())
 }
@@ -0,0 +1,103 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+
+#[path = "src/gameplay.rs"]
+mod gameplay;
+
+#[path = "../src/codec.rs"]
+mod codec;
+
+use codec::{BitPackedWriter, FieldTag};
+
+// Define constants for the directory and input file name
+const OUTPUT_DIR: &str = "build/";
+const FILE_NAME: &str = "input.bin";
+
+fn main() -> io::Result<()> {
+    println!("🔧 Flappy Bird ZisK Gameplay Input Generator");
+    println!("=====================================");
+
+    // Get command line arguments: game_id and the frames on which the bird flaps
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() != 3 {
+        println!("❌ Usage: cargo run --bin build <game_id> <flap_frames>");
+        println!("   Example: cargo run --bin build 12345 10,25,26,40");
+        println!("   <flap_frames> is a comma-separated list of frame indices where the bird flaps");
+        return Ok(());
+    }
+
+    let game_id: u64 = args[1].parse().unwrap_or(0);
+    let flap_frames: Vec<u32> = args[2]
+        .split(',')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse().unwrap_or(0))
+        .collect();
+
+    let frame_count = flap_frames
+        .iter()
+        .copied()
+        .max()
+        .map(|last| last + 1)
+        .unwrap_or(0)
+        .max(300);
+
+    let bitmask_len = (frame_count as usize + 7) / 8;
+    let mut flap_bitmask = vec![0u8; bitmask_len];
+    for &frame in &flap_frames {
+        flap_bitmask[(frame / 8) as usize] |= 1 << (frame % 8);
+    }
+
+    // Replay locally so the header score we embed always matches the guest's simulation
+    let score = gameplay::simulate(game_id, frame_count, &flap_bitmask);
+
+    println!("📊 Generating gameplay input for:");
+    println!("   Game ID: {}", game_id);
+    println!("   Frame count: {}", frame_count);
+    println!("   Simulated score: {}", score);
+
+    // Ensure the output directory exists
+    let output_dir = Path::new(OUTPUT_DIR);
+    if !output_dir.exists() {
+        println!("📁 Creating output directory: {}", OUTPUT_DIR);
+        fs::create_dir_all(output_dir)?;
+    }
+
+    // Encode the payload with the shared bit-packed codec instead of
+    // hand-placing score/game_id/bitmask at fixed byte offsets.
+    let mut writer = BitPackedWriter::new();
+    writer.write_version();
+
+    writer.write_tag(FieldTag::GameId);
+    writer.write_bits(game_id, 64);
+
+    writer.write_tag(FieldTag::FrameCount);
+    writer.write_bits(frame_count as u64, 32);
+
+    writer.write_tag(FieldTag::Score);
+    writer.write_bits(score as u64, 32);
+
+    writer.write_tag(FieldTag::FlapBitmask);
+    writer.write_uvarint(bitmask_len as u64);
+    writer.byte_align();
+    writer.write_aligned_bytes(&flap_bitmask);
+
+    let payload = writer.into_bytes();
+
+    // Create the file and write the encoded payload
+    let file_path = output_dir.join(FILE_NAME);
+    let mut file = File::create(&file_path)?;
+    file.write_all(&payload)?;
+
+    println!("💾 Input file generated: {}", file_path.display());
+    println!("📏 File size: {} bytes", file_path.metadata()?.len());
+    println!(
+        "📊 Input format: [version][game_id tag+u64][frame_count tag+u32][score tag+u32][flap_bitmask tag+len+{} bytes]",
+        bitmask_len
+    );
+    println!("✅ Input generation complete!");
+
+    Ok(())
+}
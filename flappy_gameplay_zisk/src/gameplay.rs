@@ -0,0 +1,190 @@
+// Deterministic Flappy Bird gameplay simulation.
+//
+// The guest replays the player's recorded inputs against pipes derived from
+// `game_id`, so `pipes_passed` is a genuine outcome of the run rather than a
+// number the player is free to assert.
+
+/// SplitMix64: a fast, deterministic PRNG used to derive pipe gaps from
+/// `game_id`. Not suitable for anything needing cryptographic randomness.
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+}
+
+pub const SCREEN_HEIGHT: i32 = 512;
+pub const PIPE_GAP_HEIGHT: i32 = 120;
+pub const PIPE_SPACING_FRAMES: u32 = 90;
+pub const GRAVITY: i32 = 1;
+pub const FLAP_IMPULSE: i32 = -10;
+
+/// A single pipe gap, defined by the y-coordinate of its top edge.
+#[derive(Clone, Copy, Debug)]
+struct Pipe {
+    gap_top: i32,
+}
+
+impl Pipe {
+    fn generate(rng: &mut SplitMix64) -> Self {
+        let usable_range = (SCREEN_HEIGHT - PIPE_GAP_HEIGHT) as u64;
+        let gap_top = (rng.next_u64() % usable_range) as i32;
+        Self { gap_top }
+    }
+
+    fn gap_bottom(&self) -> i32 {
+        self.gap_top + PIPE_GAP_HEIGHT
+    }
+}
+
+/// The simulated game state at a single frame.
+#[derive(Clone, Copy, Debug)]
+struct GameState {
+    bird_y: i32,
+    velocity: i32,
+    frame: u32,
+    pipes_passed: u32,
+}
+
+impl GameState {
+    fn new() -> Self {
+        Self {
+            bird_y: SCREEN_HEIGHT / 2,
+            velocity: 0,
+            frame: 0,
+            pipes_passed: 0,
+        }
+    }
+
+    /// Apply gravity and an optional flap impulse, then check for collision
+    /// with the current pipe. Returns `false` once the run has ended.
+    fn apply(&mut self, flap: bool, pipe: &Pipe) -> bool {
+        self.velocity += GRAVITY;
+        if flap {
+            self.velocity = FLAP_IMPULSE;
+        }
+        self.bird_y += self.velocity;
+        self.frame += 1;
+
+        if self.bird_y < 0 || self.bird_y > SCREEN_HEIGHT {
+            return false;
+        }
+
+        if self.frame % PIPE_SPACING_FRAMES == 0 {
+            if self.bird_y < pipe.gap_top || self.bird_y > pipe.gap_bottom() {
+                return false;
+            }
+            self.pipes_passed += 1;
+        }
+
+        true
+    }
+}
+
+/// Replay `frame_count` frames of `flap_bitmask` (bit `i` set means the bird
+/// flaps on frame `i`) against pipes generated from `game_id`, and return the
+/// number of pipes passed before a collision ends the run.
+pub fn simulate(game_id: u64, frame_count: u32, flap_bitmask: &[u8]) -> u32 {
+    let mut rng = SplitMix64::new(game_id);
+    let mut state = GameState::new();
+    let mut pipe = Pipe::generate(&mut rng);
+
+    for frame in 0..frame_count {
+        let byte = flap_bitmask[(frame / 8) as usize];
+        let flap = (byte >> (frame % 8)) & 1 == 1;
+
+        if !state.apply(flap, &pipe) {
+            break;
+        }
+
+        if state.frame % PIPE_SPACING_FRAMES == 0 {
+            pipe = Pipe::generate(&mut rng);
+        }
+    }
+
+    state.pipes_passed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splitmix64_is_deterministic_for_a_given_seed() {
+        let mut a = SplitMix64::new(42);
+        let mut b = SplitMix64::new(42);
+        for _ in 0..10 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn splitmix64_differs_across_seeds() {
+        let mut a = SplitMix64::new(1);
+        let mut b = SplitMix64::new(2);
+        assert_ne!(a.next_u64(), b.next_u64());
+    }
+
+    #[test]
+    fn pipe_generate_stays_within_the_screen() {
+        let mut rng = SplitMix64::new(12345);
+        for _ in 0..100 {
+            let pipe = Pipe::generate(&mut rng);
+            assert!(pipe.gap_top >= 0 && pipe.gap_top < SCREEN_HEIGHT - PIPE_GAP_HEIGHT);
+            assert!(pipe.gap_bottom() <= SCREEN_HEIGHT);
+        }
+    }
+
+    #[test]
+    fn game_state_apply_passes_a_pipe_it_flies_through() {
+        let pipe = Pipe { gap_top: 100 };
+        let mut state = GameState { bird_y: 150, velocity: 0, frame: 89, pipes_passed: 0 };
+
+        assert!(state.apply(false, &pipe));
+        assert_eq!(state.pipes_passed, 1);
+    }
+
+    #[test]
+    fn game_state_apply_ends_the_run_on_missing_a_pipe() {
+        let pipe = Pipe { gap_top: 300 };
+        let mut state = GameState { bird_y: 0, velocity: 0, frame: 89, pipes_passed: 0 };
+
+        assert!(!state.apply(false, &pipe));
+        assert_eq!(state.pipes_passed, 0);
+    }
+
+    #[test]
+    fn game_state_apply_ends_the_run_off_the_top_or_bottom_of_the_screen() {
+        let pipe = Pipe { gap_top: 100 };
+        let mut state = GameState { bird_y: SCREEN_HEIGHT, velocity: 5, frame: 0, pipes_passed: 0 };
+
+        assert!(!state.apply(false, &pipe));
+    }
+
+    #[test]
+    fn simulate_is_deterministic_for_a_given_game_id() {
+        let flap_bitmask = vec![0u8; 16];
+        let a = simulate(777, 100, &flap_bitmask);
+        let b = simulate(777, 100, &flap_bitmask);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn simulate_with_no_flaps_crashes_before_the_first_pipe() {
+        // Gravity alone drives the bird off the bottom of the screen well
+        // before frame 90, the first pipe-spacing checkpoint.
+        let flap_bitmask = vec![0u8; 25];
+        assert_eq!(simulate(1, 200, &flap_bitmask), 0);
+    }
+}
@@ -0,0 +1,64 @@
+#![no_main]
+ziskos::entrypoint!(main);
+
+use ziskos::{read_input, set_output};
+
+mod gameplay;
+
+#[path = "../../src/codec.rs"]
+mod codec;
+
+use codec::{BitPackedBuffer, FieldTag};
+use gameplay::simulate;
+
+fn main() {
+    // Parse the versioned, tag-prefixed payload written by build.rs
+    let input: Vec<u8> = read_input();
+
+    let mut buffer = BitPackedBuffer::new(&input);
+    buffer.read_version().unwrap_or_else(|e| panic!("{}", e));
+
+    buffer.expect_tag(FieldTag::GameId).unwrap_or_else(|e| panic!("{}", e));
+    let game_id = buffer.read_bits(64).unwrap_or_else(|e| panic!("{}", e));
+
+    buffer.expect_tag(FieldTag::FrameCount).unwrap_or_else(|e| panic!("{}", e));
+    let frame_count = buffer.read_bits(32).unwrap_or_else(|e| panic!("{}", e)) as u32;
+
+    buffer.expect_tag(FieldTag::Score).unwrap_or_else(|e| panic!("{}", e));
+    let asserted_score = buffer.read_bits(32).unwrap_or_else(|e| panic!("{}", e)) as u32;
+
+    buffer.expect_tag(FieldTag::FlapBitmask).unwrap_or_else(|e| panic!("{}", e));
+    let bitmask_len = buffer.read_uvarint().unwrap_or_else(|e| panic!("{}", e)) as usize;
+    buffer.byte_align();
+    let flap_bitmask = buffer.read_aligned_bytes(bitmask_len).unwrap_or_else(|e| panic!("{}", e));
+
+    if game_id == 0 {
+        panic!("Invalid game session ID: {}", game_id);
+    }
+
+    // bitmask_len is an independent tag-prefixed field and the codec only
+    // bounds-checks it against the total input length, not frame_count; make
+    // sure it actually covers every frame before simulate() indexes into it.
+    let required_bytes = (frame_count as u64 + 7) / 8;
+    if (bitmask_len as u64) < required_bytes {
+        panic!(
+            "flap_bitmask too short: {} bytes cannot cover {} frames (need {})",
+            bitmask_len, frame_count, required_bytes
+        );
+    }
+
+    // Re-simulate the run instead of trusting the asserted score.
+    let simulated_score = simulate(game_id, frame_count, flap_bitmask);
+
+    if simulated_score != asserted_score {
+        panic!(
+            "Score mismatch: asserted {} but simulation produced {}",
+            asserted_score, simulated_score
+        );
+    }
+
+    // Set outputs for ZisK
+    set_output(0, simulated_score);
+    set_output(1, game_id as u32);
+    set_output(2, (game_id >> 32) as u32);
+}
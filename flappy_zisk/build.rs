@@ -3,6 +3,18 @@ use std::fs::{self, File};
 use std::io::{self, Write};
 use std::path::Path;
 
+#[path = "../src/clock.rs"]
+mod clock;
+#[path = "../src/codec.rs"]
+mod codec;
+#[path = "../src/signing.rs"]
+mod signing;
+
+use clock::{Clock, SystemClock};
+use codec::{BitPackedWriter, FieldTag};
+use rand::rngs::OsRng;
+use signing::PlayerKeypair;
+
 // Define constants for the directory and input file name
 const OUTPUT_DIR: &str = "build/";
 const FILE_NAME: &str = "input.bin";
@@ -16,7 +28,7 @@ fn main() -> io::Result<()> {
             println!("  {} = {}", key, value);
         }
     }
-    
+
     // Check if GAME_SCORE is set, if not, create a placeholder file
     let game_score = match env::var("GAME_SCORE") {
         Ok(score_str) => {
@@ -56,7 +68,9 @@ fn main() -> io::Result<()> {
             }
         }
     };
-    
+
+    let system_clock = SystemClock;
+
     // Get Game ID from environment for tamper-proof binding
     let game_id = match env::var("GAME_ID") {
         Ok(id_str) => {
@@ -69,10 +83,7 @@ fn main() -> io::Result<()> {
                 Err(e) => {
                     println!("Invalid GAME_ID format '{}': {:?}, generating default", id_str, e);
                     // Generate a default game ID based on score and timestamp
-                    let timestamp = std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs();
+                    let timestamp = system_clock.now_unix();
                     let default_id = (timestamp << 20) | (game_score % 1048576);
                     println!("Generated default game_id: {}", default_id);
                     default_id
@@ -81,16 +92,13 @@ fn main() -> io::Result<()> {
         },
         Err(_) => {
             // Generate a default game ID based on score and timestamp
-            let timestamp = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
+            let timestamp = system_clock.now_unix();
             let default_id = (timestamp << 20) | (game_score % 1048576);
             println!("GAME_ID not found, generated default game_id: {}", default_id);
             default_id
         }
     };
-    
+
     println!("Final game_score value: {}", game_score);
     println!("Final game_id value: {}", game_id);
     println!("=== build.rs DEBUG END ===");
@@ -99,31 +107,60 @@ fn main() -> io::Result<()> {
     let output_dir = Path::new(OUTPUT_DIR);
     if !output_dir.exists() {
         // Create the directory and any necessary parent directories
-        fs::create_dir_all(output_dir)?; 
+        fs::create_dir_all(output_dir)?;
     }
 
-    // Create the file and write both score and game_id in little-endian format
+    // Sign the canonical score||game_id||timestamp message so the guest can
+    // verify the submission is genuinely the signer's, not just replayed
+    // from a known game_id.
+    //
+    // NOTE: this mints a fresh keypair on every run rather than loading a
+    // persisted per-player identity, so the signature only proves "whoever
+    // generated this input.bin also produced this signature" — nothing
+    // upstream of this tool registers a public key against a specific
+    // player and checks submissions against it. The signing machinery here
+    // is correct in isolation; it has no identity to bind to yet.
+    let timestamp = system_clock.now_unix();
+    let keypair = PlayerKeypair::generate(&mut OsRng);
+    let public_key = keypair.public_key_bytes();
+    let signature = keypair.sign(game_score as u32, game_id, timestamp);
+
+    // Encode the payload with the shared bit-packed codec instead of
+    // hand-placing score/game_id at fixed byte offsets.
+    let mut writer = BitPackedWriter::new();
+    writer.write_version();
+
+    writer.write_tag(FieldTag::Score);
+    writer.write_bits(game_score, 64);
+
+    writer.write_tag(FieldTag::GameId);
+    writer.write_bits(game_id, 64);
+
+    writer.write_tag(FieldTag::Timestamp);
+    writer.write_bits(timestamp, 64);
+
+    writer.write_tag(FieldTag::PublicKey);
+    writer.byte_align();
+    writer.write_aligned_bytes(&public_key);
+
+    writer.write_tag(FieldTag::Signature);
+    writer.byte_align();
+    writer.write_aligned_bytes(&signature);
+
+    let payload = writer.into_bytes();
+
+    // Create the file and write the encoded payload
     let file_path = output_dir.join(FILE_NAME);
     let mut file = File::create(&file_path)?;
-    
-    // Write score (8 bytes) - first 8 bytes
-    file.write_all(&game_score.to_le_bytes())?;
-    // Write game_id (8 bytes) - next 8 bytes
-    file.write_all(&game_id.to_le_bytes())?;
-    
+    file.write_all(&payload)?;
+
     if game_score > 0 {
         println!("Input file generated successfully at: {:?}", file_path);
-        println!("File size: {} bytes (score: {} + game_id: {})", 
-                 std::mem::size_of::<u64>() * 2, 
-                 std::mem::size_of::<u64>(), 
-                 std::mem::size_of::<u64>());
+        println!("File size: {} bytes", payload.len());
         println!("Content: Score={}, GameID={}", game_score, game_id);
     } else {
         println!("Placeholder input.bin created at: {:?}", file_path);
-        println!("File size: {} bytes (score: {} + game_id: {})", 
-                 std::mem::size_of::<u64>() * 2, 
-                 std::mem::size_of::<u64>(), 
-                 std::mem::size_of::<u64>());
+        println!("File size: {} bytes", payload.len());
     }
 
     Ok(())
@@ -1,50 +1,87 @@
 #![no_main]
 ziskos::entrypoint!(main);
 
-use std::convert::TryInto;
 use ziskos::{read_input, set_output};
 
+#[path = "../../src/clock.rs"]
+mod clock;
+#[path = "../../src/codec.rs"]
+mod codec;
+#[path = "../../src/signing.rs"]
+mod signing;
+
+use codec::{BitPackedBuffer, FieldTag};
+
 fn main() {
     // Read input safely
     let input: Vec<u8> = read_input();
-    
-    //Validate input length first
-    if input.len() != 16 {
-        panic!("Invalid input: expected 16 bytes (score + game_id), got {}", input.len());
-    }
-    
-    // Extract score safely with bounds checking
-    let score_bytes: [u8; 8] = input[0..8].try_into().unwrap();
-    let game_score: u64 = u64::from_le_bytes(score_bytes);
-    
-    // Extract game ID safely
-    let game_id_bytes: [u8; 8] = input[8..16].try_into().unwrap();
-    let game_id: u64 = u64::from_le_bytes(game_id_bytes);
-    
+
+    // Parse the versioned, tag-prefixed payload written by build.rs
+    let mut buffer = BitPackedBuffer::new(&input);
+    buffer.read_version().unwrap_or_else(|e| panic!("{}", e));
+
+    buffer.expect_tag(FieldTag::Score).unwrap_or_else(|e| panic!("{}", e));
+    let game_score: u64 = buffer.read_bits(64).unwrap_or_else(|e| panic!("{}", e));
+
+    buffer.expect_tag(FieldTag::GameId).unwrap_or_else(|e| panic!("{}", e));
+    let game_id: u64 = buffer.read_bits(64).unwrap_or_else(|e| panic!("{}", e));
+
+    buffer.expect_tag(FieldTag::Timestamp).unwrap_or_else(|e| panic!("{}", e));
+    let timestamp: u64 = buffer.read_bits(64).unwrap_or_else(|e| panic!("{}", e));
+
+    buffer.expect_tag(FieldTag::PublicKey).unwrap_or_else(|e| panic!("{}", e));
+    buffer.byte_align();
+    let public_key: [u8; 32] = buffer
+        .read_aligned_bytes(32)
+        .unwrap_or_else(|e| panic!("{}", e))
+        .try_into()
+        .unwrap();
+
+    buffer.expect_tag(FieldTag::Signature).unwrap_or_else(|e| panic!("{}", e));
+    buffer.byte_align();
+    let signature: [u8; 64] = buffer
+        .read_aligned_bytes(64)
+        .unwrap_or_else(|e| panic!("{}", e))
+        .try_into()
+        .unwrap();
+
     // Validate inputs before any complex operations
     if game_score == 0 || game_score > 1000 {
         panic!("Invalid score: {} (must be 1-1000)", game_score);
     }
-    
+
     if game_id == 0 {
         panic!("Invalid game session ID: {}", game_id);
     }
-    
-    // Simplified timestamp validation to avoid complex bit operations
+
+    // Verify the player's signature over score||game_id||timestamp before
+    // trusting any of it; a bare game_id is no longer enough to forge a proof.
+    signing::verify(&public_key, &signature, game_score as u32, game_id, timestamp)
+        .unwrap_or_else(|e| panic!("Signature verification failed: {}", e));
+
+    // The guest has no independent clock, so the game_id-derived timestamp
+    // can only be floor-checked against a fixed constant; comparing it to
+    // the player's own signed `timestamp` would be comparing attacker input
+    // to itself. A genuine "is this in the future" check belongs on the
+    // server that receives the submission, where `SystemClock` is trusted.
     let game_timestamp = game_id >> 20;
-    if game_timestamp < 1700000000 {
-        panic!("Game session timestamp appears invalid: {}", game_timestamp);
-    }
-    
+    clock::validate_min_game_timestamp(game_timestamp).unwrap_or_else(|e| panic!("{}", e));
+
     // Create proof binding safely
     let proof_binding = create_proof_binding(game_score, game_id);
-    
+
+    // Hash the public key so the proof commits to a specific player identity
+    // and can't be replayed under a different one.
+    let public_key_hash = signing::public_key_hash(&public_key);
+    let public_key_hash_prefix = u32::from_be_bytes(public_key_hash[0..4].try_into().unwrap());
+
     // Set outputs safely
     set_output(0, game_score as u32);
     set_output(1, (game_score >> 32) as u32);
     set_output(2, game_id as u32);
     set_output(3, (game_id >> 32) as u32);
     set_output(4, proof_binding);
+    set_output(5, public_key_hash_prefix);
 }
 
 // binding prevents proof replay attacks
@@ -0,0 +1,124 @@
+// Injectable time source.
+//
+// Timestamps used to come from three unrelated places: a hardcoded
+// `1234567890` in `GameScore::new` ("for ZisK compatibility"), a real
+// `SystemTime::now()` in build.rs, and a `game_id >> 20` timestamp sanity
+// check in the guest compared against a bare literal. None of that could be
+// driven deterministically in a test. `Clock` gives every one of those call
+// sites the same abstraction: production code reaches for `SystemClock`,
+// tests reach for `FixedClock`.
+
+pub trait Clock: Send + Sync {
+    fn now_unix(&self) -> u64;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_unix(&self) -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("system clock is before the Unix epoch")
+            .as_secs()
+    }
+}
+
+/// A clock that always returns the same instant, settable for tests.
+pub struct FixedClock {
+    instant: u64,
+}
+
+impl FixedClock {
+    pub fn new(instant: u64) -> Self {
+        Self { instant }
+    }
+
+    pub fn set(&mut self, instant: u64) {
+        self.instant = instant;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now_unix(&self) -> u64 {
+        self.instant
+    }
+}
+
+/// Earliest Unix timestamp a `game_id` is allowed to encode; below this the
+/// session is treated as forged or corrupted.
+pub const MIN_VALID_GAME_TIMESTAMP: u64 = 1_700_000_000;
+
+/// Validate a `game_id`-derived timestamp against `clock`: it must be recent
+/// enough to not be forged, and not claim to be from the future.
+///
+/// `clock` must be a source of time independent of the value being checked
+/// (e.g. `SystemClock` on the server that received the submission). Checking
+/// a timestamp against a `FixedClock` built from that same attacker-supplied
+/// value proves nothing — use [`validate_min_game_timestamp`] instead in
+/// contexts, like a ZisK guest, with no independent clock.
+pub fn validate_game_timestamp(game_timestamp: u64, clock: &dyn Clock) -> Result<(), String> {
+    validate_min_game_timestamp(game_timestamp)?;
+
+    let now = clock.now_unix();
+    if game_timestamp > now {
+        return Err(format!(
+            "game session timestamp is in the future: {} > {}",
+            game_timestamp, now
+        ));
+    }
+
+    Ok(())
+}
+
+/// Reject a `game_id`-derived timestamp older than [`MIN_VALID_GAME_TIMESTAMP`].
+///
+/// This is the only check available where there is no trusted "now" to
+/// compare against, such as inside a ZisK guest: the prover controls every
+/// input, so an upper (future) bound would just be checked against itself.
+pub fn validate_min_game_timestamp(game_timestamp: u64) -> Result<(), String> {
+    if game_timestamp < MIN_VALID_GAME_TIMESTAMP {
+        return Err(format!(
+            "game session timestamp appears invalid: {} (must be >= {})",
+            game_timestamp, MIN_VALID_GAME_TIMESTAMP
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_expired_timestamp() {
+        let clock = FixedClock::new(1_800_000_000);
+        assert!(validate_game_timestamp(1_600_000_000, &clock).is_err());
+    }
+
+    #[test]
+    fn rejects_future_timestamp() {
+        let clock = FixedClock::new(1_800_000_000);
+        assert!(validate_game_timestamp(1_900_000_000, &clock).is_err());
+    }
+
+    #[test]
+    fn accepts_timestamp_within_range() {
+        let clock = FixedClock::new(1_800_000_000);
+        assert!(validate_game_timestamp(1_750_000_000, &clock).is_ok());
+    }
+
+    #[test]
+    fn min_timestamp_check_rejects_expired_timestamp_without_a_clock() {
+        assert!(validate_min_game_timestamp(1_600_000_000).is_err());
+        assert!(validate_min_game_timestamp(1_700_000_000).is_ok());
+    }
+
+    #[test]
+    fn fixed_clock_can_be_advanced() {
+        let mut clock = FixedClock::new(1_700_000_000);
+        assert_eq!(clock.now_unix(), 1_700_000_000);
+        clock.set(1_800_000_000);
+        assert_eq!(clock.now_unix(), 1_800_000_000);
+    }
+}
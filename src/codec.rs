@@ -0,0 +1,334 @@
+// Shared bit-packed input codec for the Flappy Bird ZisK guests.
+//
+// `build.rs` (the encoder) and every guest `main` (the decoder) include this
+// file directly via `#[path = ...] mod codec;` rather than hand-rolling
+// matching byte offsets in four different places. Every payload starts with
+// a format version byte so a decoder can reject a mismatched encoder with a
+// clear error instead of panicking on a length check, and every field is
+// prefixed with a tag byte so a decoder can confirm it's reading the field
+// it thinks it's reading.
+
+pub const FORMAT_VERSION: u8 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodecError {
+    UnexpectedEof { wanted_bits: u32, remaining_bits: usize },
+    VersionMismatch { expected: u8, found: u8 },
+    TagMismatch { expected: u8, found: u8 },
+}
+
+impl std::fmt::Display for CodecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodecError::UnexpectedEof { wanted_bits, remaining_bits } => write!(
+                f,
+                "unexpected end of input: wanted {} bits, only {} remain",
+                wanted_bits, remaining_bits
+            ),
+            CodecError::VersionMismatch { expected, found } => write!(
+                f,
+                "input format version mismatch: expected {}, found {}",
+                expected, found
+            ),
+            CodecError::TagMismatch { expected, found } => write!(
+                f,
+                "unexpected field tag: expected {}, found {}",
+                expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+/// Field tags shared by every payload that uses this codec. Decoders check
+/// the tag before consuming a field's value so a shifted/mismatched layout
+/// fails with `CodecError::TagMismatch` instead of silently misreading bytes.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldTag {
+    GameId = 1,
+    Score = 2,
+    Difficulty = 3,
+    FrameCount = 4,
+    FlapBitmask = 5,
+    PublicKey = 6,
+    Signature = 7,
+    Timestamp = 8,
+    PlayerId = 9,
+}
+
+/// MSB-first bit reader over a borrowed byte slice, modeled on the
+/// `BitPackedBuffer` used by the SC2 replay parser: tracks how many bytes
+/// have been consumed (`used`) and the partially-consumed current byte
+/// (`next`/`nextbits`).
+pub struct BitPackedBuffer<'a> {
+    data: &'a [u8],
+    used: usize,
+    next: u8,
+    nextbits: u32,
+}
+
+impl<'a> BitPackedBuffer<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, used: 0, next: 0, nextbits: 0 }
+    }
+
+    fn remaining_bits(&self) -> usize {
+        (self.data.len() - self.used) * 8 + self.nextbits as usize
+    }
+
+    /// Read `bits` (<= 64) MSB-first, crossing byte boundaries as needed.
+    pub fn read_bits(&mut self, bits: u32) -> Result<u64, CodecError> {
+        if bits as usize > self.remaining_bits() {
+            return Err(CodecError::UnexpectedEof { wanted_bits: bits, remaining_bits: self.remaining_bits() });
+        }
+
+        let mut result: u64 = 0;
+        let mut left = bits;
+        while left > 0 {
+            if self.nextbits == 0 {
+                self.next = self.data[self.used];
+                self.used += 1;
+                self.nextbits = 8;
+            }
+            let take = left.min(self.nextbits);
+            let shift = self.nextbits - take;
+            let chunk = (self.next >> shift) & ((1u16 << take) - 1) as u8;
+            result = (result << take) | chunk as u64;
+            self.nextbits -= take;
+            left -= take;
+        }
+        Ok(result)
+    }
+
+    /// Discard any partially-read bits so the next read starts on a byte boundary.
+    pub fn byte_align(&mut self) {
+        self.nextbits = 0;
+    }
+
+    /// Read `n` bytes; the buffer must already be byte-aligned.
+    pub fn read_aligned_bytes(&mut self, n: usize) -> Result<&'a [u8], CodecError> {
+        debug_assert_eq!(self.nextbits, 0, "read_aligned_bytes called without byte_align");
+        if self.used + n > self.data.len() {
+            return Err(CodecError::UnexpectedEof { wanted_bits: n as u32 * 8, remaining_bits: self.remaining_bits() });
+        }
+        let slice = &self.data[self.used..self.used + n];
+        self.used += n;
+        Ok(slice)
+    }
+
+    /// Variable-length unsigned integer: 7 bits of value per byte, MSB of
+    /// each byte is a continuation flag.
+    pub fn read_uvarint(&mut self) -> Result<u64, CodecError> {
+        let mut result: u64 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_bits(8)? as u8;
+            result |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+        }
+        Ok(result)
+    }
+
+    /// Read and validate the 1-byte format version header.
+    pub fn read_version(&mut self) -> Result<(), CodecError> {
+        let found = self.read_bits(8)? as u8;
+        if found != FORMAT_VERSION {
+            return Err(CodecError::VersionMismatch { expected: FORMAT_VERSION, found });
+        }
+        Ok(())
+    }
+
+    /// Read a tag byte and confirm it matches `expected` before the caller reads the field value.
+    pub fn expect_tag(&mut self, expected: FieldTag) -> Result<(), CodecError> {
+        let found = self.read_bits(8)? as u8;
+        if found != expected as u8 {
+            return Err(CodecError::TagMismatch { expected: expected as u8, found });
+        }
+        Ok(())
+    }
+}
+
+/// MSB-first bit writer, the encode-side counterpart to `BitPackedBuffer`.
+pub struct BitPackedWriter {
+    data: Vec<u8>,
+    next: u8,
+    nextbits: u32,
+}
+
+impl BitPackedWriter {
+    pub fn new() -> Self {
+        Self { data: Vec::new(), next: 0, nextbits: 0 }
+    }
+
+    /// Write the low `bits` bits of `value`, MSB-first.
+    pub fn write_bits(&mut self, value: u64, bits: u32) {
+        let mut left = bits;
+        while left > 0 {
+            let free = 8 - self.nextbits;
+            let take = left.min(free);
+            let shift = left - take;
+            let chunk = ((value >> shift) & ((1u64 << take) - 1)) as u8;
+            self.next |= chunk << (free - take);
+            self.nextbits += take;
+            left -= take;
+
+            if self.nextbits == 8 {
+                self.data.push(self.next);
+                self.next = 0;
+                self.nextbits = 0;
+            }
+        }
+    }
+
+    /// Pad the current byte with zero bits so the next write starts aligned.
+    pub fn byte_align(&mut self) {
+        if self.nextbits > 0 {
+            self.data.push(self.next);
+            self.next = 0;
+            self.nextbits = 0;
+        }
+    }
+
+    pub fn write_aligned_bytes(&mut self, bytes: &[u8]) {
+        debug_assert_eq!(self.nextbits, 0, "write_aligned_bytes called without byte_align");
+        self.data.extend_from_slice(bytes);
+    }
+
+    pub fn write_uvarint(&mut self, mut value: u64) {
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                self.write_bits(byte as u64, 8);
+                break;
+            }
+            self.write_bits((byte | 0x80) as u64, 8);
+        }
+    }
+
+    pub fn write_version(&mut self) {
+        self.write_bits(FORMAT_VERSION as u64, 8);
+    }
+
+    pub fn write_tag(&mut self, tag: FieldTag) {
+        self.write_bits(tag as u64, 8);
+    }
+
+    pub fn into_bytes(mut self) -> Vec<u8> {
+        self.byte_align();
+        self.data
+    }
+}
+
+impl Default for BitPackedWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_bit_spanning_values() {
+        let mut writer = BitPackedWriter::new();
+        writer.write_bits(0b101, 3);
+        writer.write_bits(0x1234_5678_9ABC, 48);
+        writer.write_bits(0xF, 4);
+        let bytes = writer.into_bytes();
+
+        let mut reader = BitPackedBuffer::new(&bytes);
+        assert_eq!(reader.read_bits(3).unwrap(), 0b101);
+        assert_eq!(reader.read_bits(48).unwrap(), 0x1234_5678_9ABC);
+        assert_eq!(reader.read_bits(4).unwrap(), 0xF);
+    }
+
+    #[test]
+    fn round_trips_a_full_version_tag_and_field_payload() {
+        let mut writer = BitPackedWriter::new();
+        writer.write_version();
+        writer.write_tag(FieldTag::GameId);
+        writer.write_bits(42, 64);
+        let bytes = writer.into_bytes();
+
+        let mut reader = BitPackedBuffer::new(&bytes);
+        reader.read_version().unwrap();
+        reader.expect_tag(FieldTag::GameId).unwrap();
+        assert_eq!(reader.read_bits(64).unwrap(), 42);
+    }
+
+    #[test]
+    fn round_trips_multi_byte_uvarints() {
+        for value in [0u64, 1, 127, 128, 300, 1_000_000, u64::MAX] {
+            let mut writer = BitPackedWriter::new();
+            writer.write_uvarint(value);
+            let bytes = writer.into_bytes();
+
+            let mut reader = BitPackedBuffer::new(&bytes);
+            assert_eq!(reader.read_uvarint().unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn byte_align_discards_partial_bits_on_read_and_pads_on_write() {
+        let mut writer = BitPackedWriter::new();
+        writer.write_bits(0b101, 3);
+        writer.byte_align();
+        writer.write_aligned_bytes(&[0xAB, 0xCD]);
+        let bytes = writer.into_bytes();
+        assert_eq!(bytes, vec![0b1010_0000, 0xAB, 0xCD]);
+
+        let mut reader = BitPackedBuffer::new(&bytes);
+        reader.read_bits(3).unwrap();
+        reader.byte_align();
+        assert_eq!(reader.read_aligned_bytes(2).unwrap(), &[0xAB, 0xCD]);
+    }
+
+    #[test]
+    fn rejects_a_mismatched_format_version() {
+        let mut writer = BitPackedWriter::new();
+        writer.write_bits(FORMAT_VERSION as u64 + 1, 8);
+        let bytes = writer.into_bytes();
+
+        let mut reader = BitPackedBuffer::new(&bytes);
+        assert_eq!(
+            reader.read_version(),
+            Err(CodecError::VersionMismatch { expected: FORMAT_VERSION, found: FORMAT_VERSION + 1 })
+        );
+    }
+
+    #[test]
+    fn rejects_an_unexpected_field_tag() {
+        let mut writer = BitPackedWriter::new();
+        writer.write_tag(FieldTag::Score);
+        let bytes = writer.into_bytes();
+
+        let mut reader = BitPackedBuffer::new(&bytes);
+        assert_eq!(
+            reader.expect_tag(FieldTag::GameId),
+            Err(CodecError::TagMismatch { expected: FieldTag::GameId as u8, found: FieldTag::Score as u8 })
+        );
+    }
+
+    #[test]
+    fn rejects_reads_past_the_end_of_the_input() {
+        let mut reader = BitPackedBuffer::new(&[0xFF]);
+        assert_eq!(
+            reader.read_bits(16),
+            Err(CodecError::UnexpectedEof { wanted_bits: 16, remaining_bits: 8 })
+        );
+
+        let mut reader = BitPackedBuffer::new(&[0xFF]);
+        reader.byte_align();
+        assert_eq!(
+            reader.read_aligned_bytes(2),
+            Err(CodecError::UnexpectedEof { wanted_bits: 16, remaining_bits: 8 })
+        );
+    }
+}
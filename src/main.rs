@@ -7,12 +7,19 @@ ziskos::entrypoint!(main);
 
 
 use sha2::{Digest, Sha256};
-use std::convert::TryInto;
 use ziskos::{read_input, set_output};
 use byteorder::ByteOrder;
 use serde::{Deserialize, Serialize};
 // Removed chrono and uuid dependencies
 
+mod clock;
+mod codec;
+mod signing;
+mod storage;
+
+use clock::{Clock, FixedClock};
+use codec::{BitPackedBuffer, FieldTag};
+
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GameScore {
@@ -22,6 +29,9 @@ pub struct GameScore {
     pub game_session_id: String,
     pub difficulty_level: u8,
     pub proof_hash: String,
+    pub game_id: Option<u64>,
+    pub player_public_key: Option<String>, // hex-encoded ed25519 public key
+    pub signature: Option<String>,         // hex-encoded ed25519 signature
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -33,9 +43,8 @@ pub struct ScoreProof {
 }
 
 impl GameScore {
-    pub fn new(player_id: String, score: u32, difficulty_level: u8) -> Self {
-        // Use a fixed timestamp for ZisK compatibility
-        let timestamp = 1234567890u64; // Fixed timestamp
+    pub fn new(player_id: String, score: u32, difficulty_level: u8, clock: &dyn Clock) -> Self {
+        let timestamp = clock.now_unix();
         let game_session_id = format!("session_{}", score);
         
         Self {
@@ -45,9 +54,34 @@ impl GameScore {
             game_session_id,
             difficulty_level,
             proof_hash: String::new(), // Will be computed later
+            game_id: None,
+            player_public_key: None,
+            signature: None,
         }
     }
 
+    /// Record the numeric game session ID the score was signed against.
+    pub fn with_game_id(mut self, game_id: u64) -> Self {
+        self.game_id = Some(game_id);
+        self
+    }
+
+    /// Override the clock-derived timestamp, e.g. with the timestamp a
+    /// submission was actually signed against so later signature
+    /// verification checks the same message that was signed.
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Attach the player's signature over `score || game_id || timestamp` and
+    /// the public key it was signed with, hex-encoded for storage.
+    pub fn with_signature(mut self, public_key: [u8; 32], signature: [u8; 64]) -> Self {
+        self.player_public_key = Some(hex::encode(public_key));
+        self.signature = Some(hex::encode(signature));
+        self
+    }
+
     pub fn compute_proof_hash(&mut self) {
         let score_data = format!(
             "{}:{}:{}:{}:{}",
@@ -132,25 +166,25 @@ impl ScoreProof {
 fn main() {
     // Read the input data as a byte array from ziskos
     let input: Vec<u8> = read_input();
-    
-    // Parse input: [player_id_length: u8][player_id_bytes][score: u32][difficulty: u8]
-    if input.len() < 6 {
-        panic!("Invalid input length. Expected at least 6 bytes");
-    }
-    
-    let player_id_length = input[0] as usize;
-    if input.len() < 1 + player_id_length + 4 + 1 {
-        panic!("Invalid input length for player_id");
-    }
-    
-    let player_id = String::from_utf8_lossy(&input[1..1+player_id_length]).to_string();
-    let score_start = 1 + player_id_length;
-    let score_bytes: [u8; 4] = input[score_start..score_start+4].try_into().unwrap();
-    let score = u32::from_le_bytes(score_bytes);
-    let difficulty = input[score_start + 4];
-    
-    // Create game score
-    let game_score = GameScore::new(player_id, score, difficulty);
+
+    // Parse the versioned, tag-prefixed payload written by build.rs
+    let mut buffer = BitPackedBuffer::new(&input);
+    buffer.read_version().unwrap_or_else(|e| panic!("{}", e));
+
+    buffer.expect_tag(FieldTag::PlayerId).unwrap_or_else(|e| panic!("{}", e));
+    let player_id_length = buffer.read_uvarint().unwrap_or_else(|e| panic!("{}", e)) as usize;
+    buffer.byte_align();
+    let player_id_bytes = buffer.read_aligned_bytes(player_id_length).unwrap_or_else(|e| panic!("{}", e));
+    let player_id = String::from_utf8_lossy(player_id_bytes).to_string();
+
+    buffer.expect_tag(FieldTag::Score).unwrap_or_else(|e| panic!("{}", e));
+    let score = buffer.read_bits(32).unwrap_or_else(|e| panic!("{}", e)) as u32;
+
+    buffer.expect_tag(FieldTag::Difficulty).unwrap_or_else(|e| panic!("{}", e));
+    let difficulty = buffer.read_bits(8).unwrap_or_else(|e| panic!("{}", e)) as u8;
+
+    // Create game score. A fixed clock keeps the guest deterministic across provers.
+    let game_score = GameScore::new(player_id, score, difficulty, &FixedClock::new(1234567890));
     
     // Generate score proof
     let score_proof = ScoreProof::new(game_score);
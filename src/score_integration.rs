@@ -1,11 +1,30 @@
 // Score Integration Module for Flappy Bird
 // Handles score processing and proof generation
 
+use crate::clock::{Clock, SystemClock};
+use crate::signing::{self, PlayerKeypair};
+use crate::storage;
 use crate::{GameScore, ScoreProof};
+use lazy_static::lazy_static;
+use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Mutex;
-use lazy_static::lazy_static;
+
+const LEADERBOARD_DB_PATH: &str = "flappy_leaderboard.db";
+
+/// Open the leaderboard database. Tests use a fresh in-memory database
+/// instead of the real file, so `cargo test` runs start from a clean
+/// leaderboard every time instead of accumulating rows in a shared file.
+#[cfg(not(test))]
+fn open_leaderboard_db() -> rusqlite::Result<Connection> {
+    storage::init(LEADERBOARD_DB_PATH)
+}
+
+#[cfg(test)]
+fn open_leaderboard_db() -> rusqlite::Result<Connection> {
+    storage::init(":memory:")
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ScoreSubmission {
@@ -14,6 +33,9 @@ pub struct ScoreSubmission {
     pub difficulty: u8,
     pub game_session_id: Option<String>,
     pub timestamp: Option<i64>,
+    pub game_id: Option<u64>,
+    pub public_key: Option<Vec<u8>>,
+    pub signature: Option<Vec<u8>>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -34,7 +56,8 @@ pub struct LeaderboardEntry {
 }
 
 lazy_static! {
-    static ref SCORE_DATABASE: Mutex<HashMap<String, Vec<LeaderboardEntry>>> = Mutex::new(HashMap::new());
+    static ref LEADERBOARD_DB: Mutex<Connection> =
+        Mutex::new(open_leaderboard_db().expect("failed to initialize leaderboard database"));
 }
 
 impl ScoreSubmission {
@@ -45,6 +68,9 @@ impl ScoreSubmission {
             difficulty,
             game_session_id: None,
             timestamp: None,
+            game_id: None,
+            public_key: None,
+            signature: None,
         }
     }
 
@@ -58,44 +84,126 @@ impl ScoreSubmission {
         self
     }
 
+    pub fn with_game_id(mut self, game_id: u64) -> Self {
+        self.game_id = Some(game_id);
+        self
+    }
+
+    /// The timestamp to sign/verify against: whatever `with_timestamp` set,
+    /// or `clock`'s current time if the submission didn't set one.
+    pub fn resolved_timestamp(&self, clock: &dyn Clock) -> i64 {
+        self.timestamp.unwrap_or_else(|| clock.now_unix() as i64)
+    }
+
+    /// Sign `player_id || score || game_id || timestamp` with the player's
+    /// keypair, so the signature can't be replayed under a different
+    /// player's name. `game_id` defaults to 0 and `timestamp` defaults to
+    /// `clock`'s current time if not set, matching the message
+    /// `process_score`/`verify_proof` reconstruct before verifying.
+    pub fn sign(mut self, keypair: &PlayerKeypair, clock: &dyn Clock) -> Self {
+        let game_id = self.game_id.unwrap_or(0);
+        let timestamp = self.resolved_timestamp(clock) as u64;
+        let signature = keypair.sign_for_player(&self.player_id, self.score, game_id, timestamp);
+
+        self.timestamp.get_or_insert(timestamp as i64);
+        self.public_key = Some(keypair.public_key_bytes().to_vec());
+        self.signature = Some(signature.to_vec());
+        self
+    }
+
     pub fn validate(&self) -> Result<(), String> {
         if self.player_id.trim().is_empty() {
             return Err("Player ID cannot be empty".to_string());
         }
-        
+
         if self.score > 1_000_000 {
             return Err("Score seems unreasonably high".to_string());
         }
-        
+
         if self.difficulty > 10 {
             return Err("Invalid difficulty level".to_string());
         }
-        
+
+        if self.signature.is_some() != self.public_key.is_some() {
+            return Err("Signature and public key must be provided together".to_string());
+        }
+
         Ok(())
     }
 }
 
+/// Decode a hex string into a fixed-size byte array, for turning the
+/// hex-encoded public key/signature stored on `GameScore` back into the
+/// form `signing::verify` expects.
+fn decode_fixed<const N: usize>(hex_str: &str, field: &str) -> Result<[u8; N], String> {
+    let bytes = hex::decode(hex_str).map_err(|e| format!("Invalid hex for {}: {}", field, e))?;
+    bytes
+        .try_into()
+        .map_err(|_| format!("{} must decode to {} bytes", field, N))
+}
+
 pub struct ScoreManager;
 
 impl ScoreManager {
     /// Process a score submission and generate a proof
     pub fn process_score(submission: ScoreSubmission) -> Result<ScoreResponse, String> {
+        let clock = SystemClock;
+
         // Validate submission
         submission.validate()?;
-        
-        // Create game score
-        let game_score = GameScore::new(
+
+        // Resolve once so the timestamp we verify against and the one we
+        // persist onto GameScore (and later re-verify from) are the same.
+        let timestamp = submission.resolved_timestamp(&clock) as u64;
+
+        // A signed submission must verify before it's trusted
+        if let (Some(public_key), Some(signature)) = (&submission.public_key, &submission.signature) {
+            let public_key: [u8; 32] = public_key
+                .as_slice()
+                .try_into()
+                .map_err(|_| "Public key must be 32 bytes".to_string())?;
+            let signature: [u8; 64] = signature
+                .as_slice()
+                .try_into()
+                .map_err(|_| "Signature must be 64 bytes".to_string())?;
+            let game_id = submission.game_id.unwrap_or(0);
+
+            signing::verify_for_player(
+                &public_key,
+                &signature,
+                &submission.player_id,
+                submission.score,
+                game_id,
+                timestamp,
+            )
+            .map_err(|e| format!("Signature verification failed: {}", e))?;
+        }
+
+        // Create game score, carrying over the same resolved timestamp that
+        // was (or would have been) signed, not a fresh `clock.now_unix()`.
+        let mut game_score = GameScore::new(
             submission.player_id.clone(),
             submission.score,
             submission.difficulty,
-        );
-        
+            &clock,
+        )
+        .with_timestamp(timestamp);
+
+        if let Some(game_id) = submission.game_id {
+            game_score = game_score.with_game_id(game_id);
+        }
+        if let (Some(public_key), Some(signature)) = (&submission.public_key, &submission.signature) {
+            let public_key: [u8; 32] = public_key.as_slice().try_into().unwrap();
+            let signature: [u8; 64] = signature.as_slice().try_into().unwrap();
+            game_score = game_score.with_signature(public_key, signature);
+        }
+
         // Generate proof
         let score_proof = ScoreProof::new(game_score.clone());
-        
+
         // Store in leaderboard
-        let leaderboard_position = Self::add_to_leaderboard(&game_score)?;
-        
+        let leaderboard_position = Self::add_to_leaderboard(&game_score, &score_proof.public_inputs)?;
+
         Ok(ScoreResponse {
             success: true,
             proof: Some(score_proof),
@@ -103,122 +211,177 @@ impl ScoreManager {
             leaderboard_position: Some(leaderboard_position),
         })
     }
-    
-    /// Add score to leaderboard and return position
-    fn add_to_leaderboard(score: &GameScore) -> Result<u32, String> {
-        let mut db = SCORE_DATABASE.lock().map_err(|_| "Database lock error")?;
-        
-        let difficulty_key = score.difficulty_level.to_string();
-        let entries = db.entry(difficulty_key).or_insert_with(Vec::new);
-        
-        let entry = LeaderboardEntry {
-            player_id: score.player_id.clone(),
-            score: score.score,
-            difficulty: score.difficulty_level,
-            timestamp: score.timestamp.timestamp(),
-            proof_hash: score.proof_hash.clone(),
-        };
-        
-        entries.push(entry);
-        
-        // Sort by score (descending) and timestamp (ascending for ties)
-        entries.sort_by(|a, b| {
-            b.score.cmp(&a.score)
-                .then(a.timestamp.cmp(&b.timestamp))
-        });
-        
-        // Find position (1-indexed)
-        let position = entries.iter()
-            .position(|e| e.proof_hash == score.proof_hash)
-            .map(|p| p as u32 + 1)
-            .unwrap_or(1);
-        
+
+    /// Add score to leaderboard and return its 1-indexed position among
+    /// entries at the same difficulty.
+    fn add_to_leaderboard(score: &GameScore, public_inputs: &[u8]) -> Result<u32, String> {
+        let conn = LEADERBOARD_DB.lock().map_err(|_| "Database lock error")?;
+        let timestamp = score.timestamp as i64;
+
+        conn.execute(
+            "INSERT INTO leaderboard_entries (player_id, score, difficulty, timestamp, proof_hash, public_inputs)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                score.player_id,
+                score.score,
+                score.difficulty_level,
+                timestamp,
+                score.proof_hash,
+                public_inputs,
+            ],
+        )
+        .map_err(|e| format!("Failed to insert leaderboard entry: {}", e))?;
+
+        let position: u32 = conn
+            .query_row(
+                "SELECT COUNT(*) + 1 FROM leaderboard_entries
+                 WHERE difficulty = ?1 AND (score > ?2 OR (score = ?2 AND timestamp < ?3))",
+                params![score.difficulty_level, score.score, timestamp],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to compute leaderboard position: {}", e))?;
+
         Ok(position)
     }
-    
+
     /// Get leaderboard for a specific difficulty
     pub fn get_leaderboard(difficulty: u8, limit: usize) -> Result<Vec<LeaderboardEntry>, String> {
-        let db = SCORE_DATABASE.lock().map_err(|_| "Database lock error")?;
-        
-        let difficulty_key = difficulty.to_string();
-        let entries = db.get(&difficulty_key).cloned().unwrap_or_default();
-        
-        Ok(entries.into_iter().take(limit).collect())
+        let conn = LEADERBOARD_DB.lock().map_err(|_| "Database lock error")?;
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT player_id, score, difficulty, timestamp, proof_hash
+                 FROM leaderboard_entries
+                 WHERE difficulty = ?1
+                 ORDER BY score DESC, timestamp ASC
+                 LIMIT ?2",
+            )
+            .map_err(|e| e.to_string())?;
+
+        let entries = stmt
+            .query_map(params![difficulty, limit as i64], Self::row_to_entry)
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+
+        Ok(entries)
     }
-    
+
     /// Get top scores across all difficulties
     pub fn get_global_leaderboard(limit: usize) -> Result<Vec<LeaderboardEntry>, String> {
-        let db = SCORE_DATABASE.lock().map_err(|_| "Database lock error")?;
-        
-        let mut all_entries = Vec::new();
-        
-        for entries in db.values() {
-            all_entries.extend(entries.clone());
-        }
-        
-        // Sort by score (descending) and timestamp (ascending for ties)
-        all_entries.sort_by(|a, b| {
-            b.score.cmp(&a.score)
-                .then(a.timestamp.cmp(&b.timestamp))
-        });
-        
-        Ok(all_entries.into_iter().take(limit).collect())
+        let conn = LEADERBOARD_DB.lock().map_err(|_| "Database lock error")?;
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT player_id, score, difficulty, timestamp, proof_hash
+                 FROM leaderboard_entries
+                 ORDER BY score DESC, timestamp ASC
+                 LIMIT ?1",
+            )
+            .map_err(|e| e.to_string())?;
+
+        let entries = stmt
+            .query_map(params![limit as i64], Self::row_to_entry)
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+
+        Ok(entries)
     }
-    
-    /// Verify a score proof
+
+    fn row_to_entry(row: &rusqlite::Row) -> rusqlite::Result<LeaderboardEntry> {
+        Ok(LeaderboardEntry {
+            player_id: row.get(0)?,
+            score: row.get(1)?,
+            difficulty: row.get(2)?,
+            timestamp: row.get(3)?,
+            proof_hash: row.get(4)?,
+        })
+    }
+
+    /// Verify a score proof: both that the stored proof hash matches the
+    /// score data, and that the score was genuinely signed by the player
+    /// identified by `player_public_key`.
     pub fn verify_proof(proof: &ScoreProof) -> Result<bool, String> {
-        // Basic verification - in production, use proper ZisK verification
         let expected_hash = proof.score_data.proof_hash.clone();
         let mut score_copy = proof.score_data.clone();
         score_copy.compute_proof_hash();
-        
-        Ok(expected_hash == score_copy.proof_hash)
+
+        if expected_hash != score_copy.proof_hash {
+            return Ok(false);
+        }
+
+        let score_data = &proof.score_data;
+        match (&score_data.player_public_key, &score_data.signature) {
+            (Some(public_key_hex), Some(signature_hex)) => {
+                let public_key = decode_fixed::<32>(public_key_hex, "public key")?;
+                let signature = decode_fixed::<64>(signature_hex, "signature")?;
+                let game_id = score_data.game_id.unwrap_or(0);
+
+                Ok(signing::verify_for_player(
+                    &public_key,
+                    &signature,
+                    &score_data.player_id,
+                    score_data.score,
+                    game_id,
+                    score_data.timestamp,
+                )
+                .is_ok())
+            }
+            _ => Err("Proof has no signature; cannot verify player identity".to_string()),
+        }
     }
-    
+
     /// Get player statistics
     pub fn get_player_stats(player_id: &str) -> Result<PlayerStats, String> {
-        let db = SCORE_DATABASE.lock().map_err(|_| "Database lock error")?;
-        
-        let mut stats = PlayerStats {
-            player_id: player_id.to_string(),
-            total_games: 0,
-            highest_score: 0,
-            average_score: 0.0,
-            difficulty_breakdown: HashMap::new(),
-        };
-        
-        let mut total_score = 0u64;
-        
-        for (difficulty_str, entries) in db.iter() {
-            let difficulty = difficulty_str.parse::<u8>().unwrap_or(0);
-            let player_entries: Vec<_> = entries.iter()
-                .filter(|e| e.player_id == player_id)
-                .collect();
-            
-            if !player_entries.is_empty() {
-                let difficulty_stats = DifficultyStats {
-                    games_played: player_entries.len() as u32,
-                    highest_score: player_entries.iter().map(|e| e.score).max().unwrap_or(0),
-                    average_score: player_entries.iter().map(|e| e.score as u64).sum::<u64>() as f64 / player_entries.len() as f64,
-                };
-                
-                stats.difficulty_breakdown.insert(difficulty, difficulty_stats);
-                stats.total_games += player_entries.len() as u32;
-                
-                for entry in player_entries {
-                    total_score += entry.score as u64;
-                    if entry.score > stats.highest_score {
-                        stats.highest_score = entry.score;
-                    }
-                }
-            }
-        }
-        
-        if stats.total_games > 0 {
-            stats.average_score = total_score as f64 / stats.total_games as f64;
+        let conn = LEADERBOARD_DB.lock().map_err(|_| "Database lock error")?;
+
+        let (total_games, highest_score, average_score): (u32, u32, f64) = conn
+            .query_row(
+                "SELECT COUNT(*), COALESCE(MAX(score), 0), COALESCE(AVG(score), 0.0)
+                 FROM leaderboard_entries
+                 WHERE player_id = ?1",
+                params![player_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .map_err(|e| e.to_string())?;
+
+        let mut difficulty_breakdown = HashMap::new();
+        let mut stmt = conn
+            .prepare(
+                "SELECT difficulty, COUNT(*), MAX(score), AVG(score)
+                 FROM leaderboard_entries
+                 WHERE player_id = ?1
+                 GROUP BY difficulty",
+            )
+            .map_err(|e| e.to_string())?;
+
+        let rows = stmt
+            .query_map(params![player_id], |row| {
+                let difficulty: u8 = row.get(0)?;
+                Ok((
+                    difficulty,
+                    DifficultyStats {
+                        games_played: row.get(1)?,
+                        highest_score: row.get(2)?,
+                        average_score: row.get(3)?,
+                    },
+                ))
+            })
+            .map_err(|e| e.to_string())?;
+
+        for row in rows {
+            let (difficulty, stats) = row.map_err(|e| e.to_string())?;
+            difficulty_breakdown.insert(difficulty, stats);
         }
-        
-        Ok(stats)
+
+        Ok(PlayerStats {
+            player_id: player_id.to_string(),
+            total_games,
+            highest_score,
+            average_score,
+            difficulty_breakdown,
+        })
     }
 }
 
@@ -241,31 +404,72 @@ pub struct DifficultyStats {
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
     #[test]
     fn test_score_submission_validation() {
         let valid = ScoreSubmission::new("Alice".to_string(), 100, 1);
         assert!(valid.validate().is_ok());
-        
+
         let invalid_id = ScoreSubmission::new("".to_string(), 100, 1);
         assert!(invalid_id.validate().is_err());
-        
+
         let invalid_score = ScoreSubmission::new("Alice".to_string(), 2_000_000, 1);
         assert!(invalid_score.validate().is_err());
-        
+
         let invalid_difficulty = ScoreSubmission::new("Alice".to_string(), 100, 15);
         assert!(invalid_difficulty.validate().is_err());
     }
-    
+
     #[test]
     fn test_score_processing() {
         let submission = ScoreSubmission::new("Bob".to_string(), 250, 2);
         let result = ScoreManager::process_score(submission);
-        
+
         assert!(result.is_ok());
         let response = result.unwrap();
         assert!(response.success);
         assert!(response.proof.is_some());
         assert!(response.leaderboard_position.is_some());
     }
+
+    #[test]
+    fn test_signed_submission_round_trips_through_verify_proof() {
+        let clock = crate::clock::FixedClock::new(1_700_000_000);
+        let keypair = PlayerKeypair::from_bytes(&[7u8; 32]);
+
+        let submission = ScoreSubmission::new("Carol".to_string(), 400, 3)
+            .with_game_id(42)
+            .with_timestamp(clock.now_unix() as i64)
+            .sign(&keypair, &clock);
+
+        let response = ScoreManager::process_score(submission).unwrap();
+        let proof = response.proof.unwrap();
+
+        // Re-verifying later (a fresh `clock.now_unix()` at verification
+        // time) must still succeed: verification checks the signed
+        // timestamp carried on the proof, not "now".
+        assert!(ScoreManager::verify_proof(&proof).unwrap());
+    }
+
+    #[test]
+    fn test_signed_submission_rejected_under_a_different_player_id() {
+        // score, game_id, timestamp, public_key and signature are all
+        // plaintext fields an observer could copy verbatim from a public
+        // leaderboard entry or shared proof. Resubmitting them under a
+        // different player_id must not verify, or the signature wouldn't
+        // actually bind an identity.
+        let clock = crate::clock::FixedClock::new(1_700_000_000);
+        let keypair = PlayerKeypair::from_bytes(&[7u8; 32]);
+
+        let submission = ScoreSubmission::new("Carol".to_string(), 400, 3)
+            .with_game_id(42)
+            .with_timestamp(clock.now_unix() as i64)
+            .sign(&keypair, &clock);
+
+        let mut proof = ScoreManager::process_score(submission).unwrap().proof.unwrap();
+        proof.score_data.player_id = "Mallory".to_string();
+        proof.score_data.compute_proof_hash();
+
+        assert!(!ScoreManager::verify_proof(&proof).unwrap());
+    }
 }
@@ -0,0 +1,130 @@
+// Player identity and signature verification for score submissions.
+//
+// `create_proof_binding` (see `flappy_zisk`) only scrambles score+game_id,
+// which binds a proof to nothing a verifier can trust as an identity. This
+// module lets a player sign `score || game_id || timestamp` with an ed25519
+// keypair; the guest verifies that signature before trusting the run, and
+// commits to a hash of the public key so the resulting proof can't be
+// replayed under a different player's identity.
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256};
+
+#[derive(Debug)]
+pub enum SigningError {
+    InvalidPublicKey,
+    InvalidSignature,
+    SignatureVerificationFailed,
+}
+
+impl std::fmt::Display for SigningError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SigningError::InvalidPublicKey => write!(f, "malformed ed25519 public key"),
+            SigningError::InvalidSignature => write!(f, "malformed ed25519 signature"),
+            SigningError::SignatureVerificationFailed => write!(f, "signature does not match message"),
+        }
+    }
+}
+
+impl std::error::Error for SigningError {}
+
+/// A player's ed25519 keypair, used to sign score submissions.
+pub struct PlayerKeypair {
+    signing_key: SigningKey,
+}
+
+impl PlayerKeypair {
+    pub fn generate<R: rand_core::RngCore + rand_core::CryptoRng>(rng: &mut R) -> Self {
+        Self { signing_key: SigningKey::generate(rng) }
+    }
+
+    pub fn from_bytes(secret: &[u8; 32]) -> Self {
+        Self { signing_key: SigningKey::from_bytes(secret) }
+    }
+
+    pub fn public_key_bytes(&self) -> [u8; 32] {
+        self.signing_key.verifying_key().to_bytes()
+    }
+
+    /// Sign the canonical `score || game_id || timestamp` message.
+    pub fn sign(&self, score: u32, game_id: u64, timestamp: u64) -> [u8; 64] {
+        let message = canonical_message(score, game_id, timestamp);
+        self.signing_key.sign(&message).to_bytes()
+    }
+
+    /// Sign `score || game_id || timestamp` together with a hash of
+    /// `player_id`, so the signature can't be replayed under a different
+    /// player's name. Use this instead of [`Self::sign`] wherever the
+    /// submission carries a free-text `player_id` a caller controls.
+    pub fn sign_for_player(&self, player_id: &str, score: u32, game_id: u64, timestamp: u64) -> [u8; 64] {
+        let message = canonical_message_for_player(player_id, score, game_id, timestamp);
+        self.signing_key.sign(&message).to_bytes()
+    }
+}
+
+/// The exact byte layout that gets signed and verified: `score || game_id || timestamp`.
+pub fn canonical_message(score: u32, game_id: u64, timestamp: u64) -> Vec<u8> {
+    let mut message = Vec::with_capacity(4 + 8 + 8);
+    message.extend_from_slice(&score.to_le_bytes());
+    message.extend_from_slice(&game_id.to_le_bytes());
+    message.extend_from_slice(&timestamp.to_le_bytes());
+    message
+}
+
+/// `canonical_message` extended with a hash of `player_id`. `player_id` is a
+/// free-text field the leaderboard stores verbatim alongside an otherwise
+/// plaintext, non-secret `(score, game_id, timestamp, public_key, signature)`
+/// tuple; without it in the signed message, anyone who observes one valid
+/// submission could resubmit it under a different player's name.
+pub fn canonical_message_for_player(player_id: &str, score: u32, game_id: u64, timestamp: u64) -> Vec<u8> {
+    let mut message = canonical_message(score, game_id, timestamp);
+    let mut hasher = Sha256::new();
+    hasher.update(player_id.as_bytes());
+    message.extend_from_slice(&hasher.finalize());
+    message
+}
+
+/// Verify `signature` over the canonical message for `(score, game_id, timestamp)`
+/// against `public_key`. Callers that need a hard failure (the guest) should
+/// `panic!` on `Err`; host-side callers can surface it as a `Result`.
+pub fn verify(
+    public_key: &[u8; 32],
+    signature: &[u8; 64],
+    score: u32,
+    game_id: u64,
+    timestamp: u64,
+) -> Result<(), SigningError> {
+    let verifying_key = VerifyingKey::from_bytes(public_key).map_err(|_| SigningError::InvalidPublicKey)?;
+    let signature = Signature::from_bytes(signature);
+    let message = canonical_message(score, game_id, timestamp);
+    verifying_key
+        .verify(&message, &signature)
+        .map_err(|_| SigningError::SignatureVerificationFailed)
+}
+
+/// Like [`verify`], but over [`canonical_message_for_player`] so the
+/// signature is checked against the claimed `player_id` as well.
+pub fn verify_for_player(
+    public_key: &[u8; 32],
+    signature: &[u8; 64],
+    player_id: &str,
+    score: u32,
+    game_id: u64,
+    timestamp: u64,
+) -> Result<(), SigningError> {
+    let verifying_key = VerifyingKey::from_bytes(public_key).map_err(|_| SigningError::InvalidPublicKey)?;
+    let signature = Signature::from_bytes(signature);
+    let message = canonical_message_for_player(player_id, score, game_id, timestamp);
+    verifying_key
+        .verify(&message, &signature)
+        .map_err(|_| SigningError::SignatureVerificationFailed)
+}
+
+/// SHA-256 hash of a public key, committed to outputs so a proof is
+/// non-transferable between players.
+pub fn public_key_hash(public_key: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(public_key);
+    hasher.finalize().into()
+}
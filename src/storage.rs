@@ -0,0 +1,36 @@
+// SQLite-backed leaderboard storage.
+//
+// The leaderboard used to live in a `lazy_static! Mutex<HashMap<...>>`, so
+// every score, position and `PlayerStats` evaporated on restart. This module
+// opens a real SQLite database and runs versioned migrations so the schema
+// can evolve without hand-written `ALTER TABLE` scripts scattered through
+// the codebase.
+
+use rusqlite::Connection;
+use rusqlite_migration::{Migrations, M};
+
+/// Versioned schema migrations, applied in order by `init`.
+fn migrations() -> Migrations<'static> {
+    Migrations::new(vec![M::up(
+        "CREATE TABLE leaderboard_entries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            player_id TEXT NOT NULL,
+            score INTEGER NOT NULL,
+            difficulty INTEGER NOT NULL,
+            timestamp INTEGER NOT NULL,
+            proof_hash TEXT NOT NULL,
+            public_inputs BLOB NOT NULL
+        );
+        CREATE INDEX idx_leaderboard_difficulty_score ON leaderboard_entries (difficulty, score);",
+    )])
+}
+
+/// Open (creating if needed) the leaderboard database at `db_path` and bring
+/// its schema up to the latest migration.
+pub fn init(db_path: &str) -> rusqlite::Result<Connection> {
+    let mut conn = Connection::open(db_path)?;
+    migrations()
+        .to_latest(&mut conn)
+        .expect("leaderboard database migrations failed");
+    Ok(conn)
+}